@@ -0,0 +1,151 @@
+use rustpython_parser::ast::{Expr, Location, Stmt, StmtKind};
+
+use crate::core::helpers::is_elif;
+use crate::core::locator::Locator;
+use crate::core::types::Range;
+
+/// A single branch of a flattened `if`/`elif`/`else` chain.
+#[derive(Debug, Clone, Copy)]
+pub struct Branch<'a> {
+    /// The branch's condition, or `None` for a final `else`.
+    pub condition: Option<&'a Expr>,
+    /// The branch's body statements.
+    pub body: &'a [Stmt],
+    /// The source range from the branch's header (`if`/`elif`/`else`)
+    /// through the end of its body.
+    pub range: Range,
+}
+
+/// Decompose an `if` statement's full `elif`/`else` chain into an ordered
+/// list of [`Branch`]es, by repeatedly following `orelse` for as long as it
+/// is a single nested `If` that [`is_elif`] textually.
+///
+/// This mirrors how other compilers represent a conditional as a list of
+/// `(condition, body)` pairs plus an optional final `else`, sparing callers
+/// from recursing through `orelse` and re-checking `is_elif` at every level.
+/// Returns an empty `Vec` if `stmt` isn't an `If`.
+pub fn if_branches<'a>(stmt: &'a Stmt, locator: &Locator) -> Vec<Branch<'a>> {
+    let StmtKind::If {
+        test,
+        body,
+        orelse,
+    } = &stmt.node
+    else {
+        return Vec::new();
+    };
+
+    let mut branches = Vec::new();
+    let mut location = stmt.location;
+    let mut test = test;
+    let mut body = body.as_slice();
+    let mut orelse = orelse.as_slice();
+
+    loop {
+        let end = body
+            .last()
+            .map_or(location, |last| last.end_location.unwrap());
+        branches.push(Branch {
+            condition: Some(test),
+            body,
+            range: Range::new(location, end),
+        });
+
+        if is_elif(orelse, locator) {
+            let StmtKind::If {
+                test: next_test,
+                body: next_body,
+                orelse: next_orelse,
+            } = &orelse[0].node
+            else {
+                unreachable!("is_elif guarantees a single nested `If`");
+            };
+            location = orelse[0].location;
+            test = next_test;
+            body = next_body.as_slice();
+            orelse = next_orelse.as_slice();
+            continue;
+        }
+
+        if let [first, ..] = orelse {
+            let header = else_header_location(end, first.location, locator);
+            let body_end = orelse.last().unwrap().end_location.unwrap();
+            branches.push(Branch {
+                condition: None,
+                body: orelse,
+                range: Range::new(header, body_end),
+            });
+        }
+        break;
+    }
+
+    branches
+}
+
+/// Recover the location of the `else` keyword that introduces a final-else
+/// branch. The AST has no node for the keyword itself (only for the `orelse`
+/// body), so this scans the source between the end of the preceding branch's
+/// body and the start of the `else` body for the last `else` substring.
+/// Falls back to `body_start` (i.e., an empty, header-less range) if `else`
+/// can't be found, which only happens on malformed or unusual source.
+fn else_header_location(after_body: Location, body_start: Location, locator: &Locator) -> Location {
+    let (source, start, end) = locator.slice(Range::new(after_body, body_start));
+    let between = &source[start..end];
+
+    let Some(byte_offset) = between.rfind("else") else {
+        return body_start;
+    };
+
+    let mut row = after_body.row();
+    let mut column = after_body.column();
+    for c in between[..byte_offset].chars() {
+        if c == '\n' {
+            row += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+    }
+    Location::new(row, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use rustpython_parser::ast::StmtKind;
+    use rustpython_parser::parser::parse_program;
+
+    use crate::core::locator::Locator;
+
+    use super::if_branches;
+
+    fn branch_conditions(source: &str) -> Vec<bool> {
+        let suite = parse_program(source, "<test>").unwrap();
+        let locator = Locator::new(source);
+        let StmtKind::If { .. } = &suite[0].node else {
+            panic!("expected an `If` statement");
+        };
+        if_branches(&suite[0], &locator)
+            .iter()
+            .map(|branch| branch.condition.is_some())
+            .collect()
+    }
+
+    #[test]
+    fn bare_if_is_a_single_branch() {
+        assert_eq!(branch_conditions("if x:\n    pass\n"), vec![true]);
+    }
+
+    #[test]
+    fn if_elif_elif_else_is_four_branches() {
+        let source = "if a:\n    pass\nelif b:\n    pass\nelif c:\n    pass\nelse:\n    pass\n";
+        assert_eq!(branch_conditions(source), vec![true, true, true, false]);
+    }
+
+    #[test]
+    fn nested_if_in_else_does_not_flatten() {
+        // `else:` followed by a nested `if` (not textually `elif`) is a
+        // single `else` branch whose body happens to be another `if`
+        // statement, not a third flattened branch.
+        let source = "if a:\n    pass\nelse:\n    if b:\n        pass\n";
+        assert_eq!(branch_conditions(source), vec![true, false]);
+    }
+}
@@ -0,0 +1,130 @@
+use rustpython_parser::ast::Location;
+
+use crate::core::helpers::{leading_quote, trailing_quote};
+use crate::core::types::Range;
+
+/// The result of normalizing a docstring's inner text per PEP 257.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedDocstring {
+    /// The dedented body, with the prefix/quotes and any leading or
+    /// trailing blank lines stripped.
+    pub normalized: String,
+    /// The source range covered by `normalized`.
+    pub range: Range,
+    /// The source `Location` of the first character of each line of
+    /// `normalized`, in order.
+    pub line_offsets: Vec<Location>,
+}
+
+/// Extract and normalize the docstring held by a string literal.
+///
+/// `contents` is the full literal, quotes and all (e.g.
+/// `'''   Summary.\n\n    Body.   '''`); `location` is its start in the
+/// source. Building on [`leading_quote`]/[`trailing_quote`], this strips the
+/// prefix and quotes, then applies PEP 257's normalization: the first line's
+/// leading whitespace is stripped, the minimum common leading whitespace of
+/// every other line is computed and stripped from each of those lines, and
+/// leading/trailing blank lines are dropped. Returns `None` if `contents`
+/// isn't recognizable as a quoted string literal, or if every line of its
+/// body is blank.
+pub fn normalize_docstring(contents: &str, location: Location) -> Option<NormalizedDocstring> {
+    let leading = leading_quote(contents)?;
+    let trailing = *trailing_quote(contents)?;
+    // A malformed literal (e.g. unterminated `"""`) can have its opening
+    // quotes double as the matched trailing quote, which would otherwise
+    // make this slice's `start > end` and panic.
+    if contents.len() < leading.len() + trailing.len() {
+        return None;
+    }
+    let inner = &contents[leading.len()..contents.len() - trailing.len()];
+    let inner_start = Location::new(location.row(), location.column() + leading.len());
+
+    let raw_lines: Vec<&str> = inner.split('\n').collect();
+
+    // The minimum indentation of every line but the first, ignoring blank
+    // lines, per PEP 257.
+    let min_indent = raw_lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| leading_whitespace_len(line))
+        .min()
+        .unwrap_or(0);
+
+    // Dedent every line but the first, and track each line's source column.
+    let mut lines: Vec<(usize, String)> = Vec::with_capacity(raw_lines.len());
+    for (index, line) in raw_lines.iter().enumerate() {
+        if index == 0 {
+            lines.push((leading_whitespace_len(line), line.trim_start().to_string()));
+        } else {
+            let strip = min_indent.min(leading_whitespace_len(line));
+            lines.push((strip, line[strip..].to_string()));
+        }
+    }
+
+    // Drop leading and trailing blank lines.
+    let first = lines.iter().position(|(_, line)| !line.trim().is_empty())?;
+    let last = lines.iter().rposition(|(_, line)| !line.trim().is_empty())?;
+    let lines = &lines[first..=last];
+
+    let mut line_offsets = Vec::with_capacity(lines.len());
+    let mut normalized_lines = Vec::with_capacity(lines.len());
+    for (offset_index, (strip, text)) in lines.iter().enumerate() {
+        let raw_index = first + offset_index;
+        let row = inner_start.row() + raw_index;
+        let column = if raw_index == 0 { inner_start.column() + strip } else { *strip };
+        line_offsets.push(Location::new(row, column));
+        normalized_lines.push(text.clone());
+    }
+
+    let range_end = {
+        let last_offset = *line_offsets.last().unwrap();
+        let last_len = normalized_lines.last().unwrap().len();
+        Location::new(last_offset.row(), last_offset.column() + last_len)
+    };
+
+    Some(NormalizedDocstring {
+        normalized: normalized_lines.join("\n"),
+        range: Range::new(line_offsets[0], range_end),
+        line_offsets,
+    })
+}
+
+fn leading_whitespace_len(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use rustpython_parser::ast::Location;
+
+    use super::normalize_docstring;
+
+    #[test]
+    fn dedents_and_trims_blank_lines() {
+        let contents = "\"\"\"Summary.\n\n    Indented body.\n    More body.\n    \"\"\"";
+        let docstring = normalize_docstring(contents, Location::new(1, 0)).unwrap();
+        assert_eq!(docstring.normalized, "Summary.\n\nIndented body.\nMore body.");
+    }
+
+    #[test]
+    fn non_string_literal_returns_none() {
+        assert!(normalize_docstring("not a string", Location::new(1, 0)).is_none());
+    }
+
+    #[test]
+    fn malformed_literal_returns_none_instead_of_panicking() {
+        assert!(normalize_docstring("\"\"\"\"", Location::new(1, 0)).is_none());
+    }
+
+    #[test]
+    fn first_line_offset_points_past_its_leading_whitespace() {
+        let contents = "\"\"\"  Hi\n  Bye\"\"\"";
+        let docstring = normalize_docstring(contents, Location::new(1, 0)).unwrap();
+        assert_eq!(docstring.normalized, "Hi\nBye");
+        // Column 5 is `H`: 3 quote chars + 2 spaces of leading whitespace,
+        // not the whitespace itself.
+        assert_eq!(docstring.line_offsets[0].row(), 1);
+        assert_eq!(docstring.line_offsets[0].column(), 5);
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+
+use rustpython_parser::ast::{Excepthandler, ExcepthandlerKind, Expr, ExprKind, Location, Stmt, StmtKind};
+
+use crate::core::helpers::expand_indented_block;
+use crate::core::locator::Locator;
+use crate::core::token_stream::{TokenKind, TokenStream};
+use crate::core::types::Range;
+
+/// The kind of region a [`Fold`] covers, mirroring the categories an IDE's
+/// folding provider typically distinguishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldKind {
+    /// The body of a compound statement (`if`, `for`, `def`, `class`, ...).
+    Block,
+    /// A run of consecutive `import`/`from ... import` statements.
+    Imports,
+    /// A run of consecutive comment-only lines.
+    Comment,
+    /// A multi-line collection or call literal, e.g. `[...]`, `{...}`, `f(...)`.
+    Region,
+}
+
+/// A single collapsible region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fold {
+    pub kind: FoldKind,
+    pub range: Range,
+}
+
+/// Walk `body` (typically a module's top-level statements) and collect every
+/// foldable region within it, including nested ones.
+pub fn fold_ranges(body: &[Stmt], locator: &Locator) -> Vec<Fold> {
+    let mut folds = Vec::new();
+    fold_body(body, locator, &mut folds);
+    collect_comment_folds(locator, &mut folds);
+    folds
+}
+
+fn fold_body(body: &[Stmt], locator: &Locator, folds: &mut Vec<Fold>) {
+    let mut index = 0;
+    while index < body.len() {
+        let stmt = &body[index];
+
+        // A run of consecutive imports folds as a single region.
+        if is_import(stmt) {
+            let start = index;
+            while index < body.len() && is_import(&body[index]) {
+                index += 1;
+            }
+            if index - start > 1 {
+                let last = &body[index - 1];
+                folds.push(Fold {
+                    kind: FoldKind::Imports,
+                    range: Range::new(stmt.location, last.end_location.unwrap()),
+                });
+            }
+            continue;
+        }
+
+        if let Some(fold_body_stmts) = primary_body(stmt) {
+            if let Some(last) = fold_body_stmts.last() {
+                if let Some((start, end)) =
+                    expand_indented_block(stmt.location, last.end_location.unwrap(), locator)
+                {
+                    folds.push(Fold {
+                        kind: FoldKind::Block,
+                        range: Range::new(start, end),
+                    });
+                }
+            }
+        }
+
+        collect_literal_folds_in_stmt(stmt, folds);
+
+        for nested in nested_bodies(stmt) {
+            fold_body(nested, locator, folds);
+        }
+
+        index += 1;
+    }
+}
+
+fn is_import(stmt: &Stmt) -> bool {
+    matches!(stmt.node, StmtKind::Import { .. } | StmtKind::ImportFrom { .. })
+}
+
+/// The body whose end marks the end of `stmt`'s own compound block (i.e.,
+/// the statements directly after the `:` that opened `stmt`), if any.
+fn primary_body(stmt: &Stmt) -> Option<&[Stmt]> {
+    match &stmt.node {
+        StmtKind::If { body, .. }
+        | StmtKind::For { body, .. }
+        | StmtKind::AsyncFor { body, .. }
+        | StmtKind::While { body, .. }
+        | StmtKind::With { body, .. }
+        | StmtKind::AsyncWith { body, .. }
+        | StmtKind::Try { body, .. }
+        | StmtKind::FunctionDef { body, .. }
+        | StmtKind::AsyncFunctionDef { body, .. }
+        | StmtKind::ClassDef { body, .. } => Some(body),
+        _ => None,
+    }
+}
+
+/// Every nested statement list reachable from `stmt`, for the purposes of
+/// recursing into inner scopes (imports, literals, and their own compound
+/// blocks; comments are collected separately from the whole source). This
+/// is broader than [`primary_body`], which only
+/// cares about the block that ends `stmt`'s own header.
+fn nested_bodies(stmt: &Stmt) -> Vec<&[Stmt]> {
+    match &stmt.node {
+        StmtKind::If { body, orelse, .. }
+        | StmtKind::For { body, orelse, .. }
+        | StmtKind::AsyncFor { body, orelse, .. }
+        | StmtKind::While { body, orelse, .. } => vec![body, orelse],
+        StmtKind::With { body, .. } | StmtKind::AsyncWith { body, .. } => vec![body],
+        StmtKind::Try {
+            body,
+            handlers,
+            orelse,
+            finalbody,
+        } => {
+            let mut bodies = vec![body.as_slice(), orelse.as_slice(), finalbody.as_slice()];
+            for handler in handlers {
+                let Excepthandler {
+                    node: ExcepthandlerKind::ExceptHandler { body, .. },
+                    ..
+                } = handler;
+                bodies.push(body);
+            }
+            bodies
+        }
+        StmtKind::FunctionDef { body, .. }
+        | StmtKind::AsyncFunctionDef { body, .. }
+        | StmtKind::ClassDef { body, .. } => vec![body],
+        _ => vec![],
+    }
+}
+
+/// Collect folds for any multi-line collection or call literal that appears
+/// as a direct expression of `stmt` (e.g. an assigned value, a bare
+/// expression statement, or a `return` value), recursing into their
+/// elements and arguments. This covers the common cases; literals buried in
+/// arbitrary nested expressions (e.g. inside a `BoolOp`) are left alone.
+fn collect_literal_folds_in_stmt(stmt: &Stmt, folds: &mut Vec<Fold>) {
+    let exprs: Vec<&Expr> = match &stmt.node {
+        StmtKind::Expr { value } => vec![value],
+        StmtKind::Assign { value, .. } => vec![value],
+        StmtKind::AugAssign { value, .. } => vec![value],
+        StmtKind::AnnAssign {
+            value: Some(value), ..
+        } => vec![value],
+        StmtKind::Return { value: Some(value) } => vec![value],
+        _ => vec![],
+    };
+    for expr in exprs {
+        collect_literal_folds_in_expr(expr, folds);
+    }
+}
+
+fn collect_literal_folds_in_expr(expr: &Expr, folds: &mut Vec<Fold>) {
+    let is_multiline = expr.location.row() != expr.end_location.unwrap().row();
+
+    match &expr.node {
+        ExprKind::List { elts } | ExprKind::Set { elts } | ExprKind::Tuple { elts } => {
+            if is_multiline {
+                push_region(expr, folds);
+            }
+            for elt in elts {
+                collect_literal_folds_in_expr(elt, folds);
+            }
+        }
+        ExprKind::Dict { keys, values } => {
+            if is_multiline {
+                push_region(expr, folds);
+            }
+            for key in keys.iter().flatten() {
+                collect_literal_folds_in_expr(key, folds);
+            }
+            for value in values {
+                collect_literal_folds_in_expr(value, folds);
+            }
+        }
+        ExprKind::Call {
+            func,
+            args,
+            keywords,
+        } => {
+            if is_multiline {
+                push_region(expr, folds);
+            }
+            collect_literal_folds_in_expr(func, folds);
+            for arg in args {
+                collect_literal_folds_in_expr(arg, folds);
+            }
+            for keyword in keywords {
+                collect_literal_folds_in_expr(&keyword.node.value, folds);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_region(expr: &Expr, folds: &mut Vec<Fold>) {
+    folds.push(Fold {
+        kind: FoldKind::Region,
+        range: Range::new(expr.location, expr.end_location.unwrap()),
+    });
+}
+
+/// Collect folds for every run of two or more consecutive comment-only
+/// lines in the source. Comments don't appear in the AST, so this operates
+/// directly on the source text rather than the parsed tree -- but it's
+/// driven by the tokenizer (not a raw `#` scan) so that a `#`-prefixed line
+/// sitting inside a triple-quoted string (e.g. commented-out code in a
+/// docstring) isn't mistaken for a real comment.
+fn collect_comment_folds(locator: &Locator, folds: &mut Vec<Fold>) {
+    let contents = locator.contents();
+    let lines: Vec<&str> = contents.lines().collect();
+    let string_literal_rows = string_literal_rows(contents);
+
+    let mut start: Option<usize> = None;
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with('#') && !string_literal_rows.contains(&index) {
+            if start.is_none() {
+                start = Some(index);
+            }
+        } else if let Some(first) = start.take() {
+            push_comment_fold(&lines, first, index - 1, folds);
+        }
+    }
+    if let Some(first) = start {
+        push_comment_fold(&lines, first, lines.len() - 1, folds);
+    }
+
+    fn push_comment_fold(lines: &[&str], first: usize, last: usize, folds: &mut Vec<Fold>) {
+        if last > first {
+            folds.push(Fold {
+                kind: FoldKind::Comment,
+                range: Range::new(
+                    Location::new(first + 1, 0),
+                    Location::new(last + 1, lines[last].len()),
+                ),
+            });
+        }
+    }
+}
+
+/// The set of 0-indexed line numbers that fall anywhere inside a string
+/// literal token. Errors from the tokenizer are ignored here (rows simply
+/// won't be excluded), consistent with this function's best-effort role.
+fn string_literal_rows(contents: &str) -> HashSet<usize> {
+    let mut rows = HashSet::new();
+    for token in TokenStream::new(contents, Location::new(1, 0)) {
+        if token.kind != TokenKind::StringLiteral {
+            continue;
+        }
+        for row in token.start.row()..=token.end.row() {
+            rows.insert(row - 1);
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use rustpython_parser::parser::parse_program;
+
+    use crate::core::locator::Locator;
+
+    use super::{fold_ranges, Fold, FoldKind};
+
+    fn folds(source: &str) -> Vec<Fold> {
+        let suite = parse_program(source, "<test>").unwrap();
+        let locator = Locator::new(source);
+        fold_ranges(&suite, &locator)
+    }
+
+    #[test]
+    fn folds_a_compound_statement_block() {
+        let folds = folds("if x:\n    pass\n    pass\n");
+        assert!(folds.iter().any(|fold| fold.kind == FoldKind::Block));
+    }
+
+    #[test]
+    fn folds_a_run_of_two_or_more_imports_but_not_a_single_one() {
+        let multiple = folds("import os\nimport sys\n");
+        assert!(multiple.iter().any(|fold| fold.kind == FoldKind::Imports));
+
+        let single = folds("import os\nx = 1\n");
+        assert!(!single.iter().any(|fold| fold.kind == FoldKind::Imports));
+    }
+
+    #[test]
+    fn folds_a_multiline_collection_literal() {
+        let folds = folds("x = [\n    1,\n    2,\n]\n");
+        assert!(folds.iter().any(|fold| fold.kind == FoldKind::Region));
+    }
+
+    #[test]
+    fn does_not_fold_hash_lines_inside_a_docstring() {
+        let folds = folds("\"\"\"\n# not a comment\n# still not one\n\"\"\"\n");
+        assert!(!folds.iter().any(|fold| fold.kind == FoldKind::Comment));
+    }
+
+    #[test]
+    fn folds_a_real_comment_run() {
+        let folds = folds("# one\n# two\nx = 1\n");
+        assert!(folds.iter().any(|fold| fold.kind == FoldKind::Comment));
+    }
+}
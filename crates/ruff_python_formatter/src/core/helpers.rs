@@ -1,6 +1,7 @@
 use rustpython_parser::ast::Location;
 
 use crate::core::locator::Locator;
+use crate::core::token_stream::{TokenKind, TokenStream};
 use crate::core::types::Range;
 
 /// Return the leading quote for a string or byte literal (e.g., `"""`).
@@ -41,11 +42,14 @@ pub fn is_radix_literal(content: &str) -> bool {
 ///
 /// `location` is the start of the compound statement (e.g., the `if` in `if x:`).
 /// `end_location` is the end of the last statement in the body.
+///
+/// Returns `None` if the header between `location` and `end_location` doesn't
+/// lex cleanly (e.g. on malformed input), rather than panicking.
 pub fn expand_indented_block(
     location: Location,
     end_location: Location,
     locator: &Locator,
-) -> (Location, Location) {
+) -> Option<(Location, Location)> {
     let contents = locator.contents();
     let start_index = locator.index(location);
     let end_index = locator.index(end_location);
@@ -53,45 +57,31 @@ pub fn expand_indented_block(
     // Find the colon, which indicates the end of the header.
     let mut nesting = 0;
     let mut colon = None;
-    for (start, tok, _end) in rustpython_parser::lexer::lex_located(
-        &contents[start_index..end_index],
-        rustpython_parser::Mode::Module,
-        location,
-    )
-    .flatten()
-    {
-        match tok {
-            rustpython_parser::Tok::Colon if nesting == 0 => {
-                colon = Some(start);
+    for token in TokenStream::new(&contents[start_index..end_index], location) {
+        if token.is_error {
+            return None;
+        }
+        match token.kind {
+            TokenKind::Colon if nesting == 0 => {
+                colon = Some(token.start);
                 break;
             }
-            rustpython_parser::Tok::Lpar
-            | rustpython_parser::Tok::Lsqb
-            | rustpython_parser::Tok::Lbrace => nesting += 1,
-            rustpython_parser::Tok::Rpar
-            | rustpython_parser::Tok::Rsqb
-            | rustpython_parser::Tok::Rbrace => nesting -= 1,
+            TokenKind::OpenBracket => nesting += 1,
+            TokenKind::CloseBracket => nesting -= 1,
             _ => {}
         }
     }
-    let colon_location = colon.unwrap();
+    let colon_location = colon?;
     let colon_index = locator.index(colon_location);
 
     // From here, we have two options: simple statement or compound statement.
-    let indent = rustpython_parser::lexer::lex_located(
-        &contents[colon_index..end_index],
-        rustpython_parser::Mode::Module,
-        colon_location,
-    )
-    .flatten()
-    .find_map(|(start, tok, _end)| match tok {
-        rustpython_parser::Tok::Indent => Some(start),
-        _ => None,
-    });
+    let indent = TokenStream::new(&contents[colon_index..end_index], colon_location)
+        .take_while(|token| !token.is_error)
+        .find_map(|token| matches!(token.kind, TokenKind::Indent).then_some(token.start));
 
     let Some(indent_location) = indent else {
         // Simple statement: from the colon to the end of the line.
-        return (colon_location, Location::new(end_location.row() + 1, 0));
+        return Some((colon_location, Location::new(end_location.row() + 1, 0)));
     };
 
     // Compound statement: from the colon to the end of the block.
@@ -113,7 +103,7 @@ pub fn expand_indented_block(
     }
 
     let end_location = Location::new(end_location.row() + 1 + offset, 0);
-    (colon_location, end_location)
+    Some((colon_location, end_location))
 }
 
 /// Return true if the `orelse` block of an `if` statement is an `elif` statement.
@@ -123,7 +113,19 @@ pub fn is_elif(orelse: &[rustpython_parser::ast::Stmt], locator: &Locator) -> bo
             orelse[0].location,
             orelse[0].end_location.unwrap(),
         ));
-        if source[start..end].starts_with("elif") {
+        let text = &source[start..end];
+
+        // Bail out conservatively if the first token doesn't even lex cleanly,
+        // rather than trusting a textual prefix match against malformed
+        // source.
+        let Some(first) = TokenStream::new(text, orelse[0].location).next() else {
+            return false;
+        };
+        if first.is_error {
+            return false;
+        }
+
+        if text.starts_with("elif") {
             return true;
         }
     }
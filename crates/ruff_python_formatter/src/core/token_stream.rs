@@ -0,0 +1,110 @@
+use rustpython_parser::ast::Location;
+use rustpython_parser::lexer::LexResult;
+use rustpython_parser::{lexer, Mode, Tok};
+
+use crate::core::types::Range;
+
+/// A coarse classification of a lexical token, covering only the kinds that
+/// consumers in this crate currently need to distinguish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Colon,
+    OpenBracket,
+    CloseBracket,
+    Indent,
+    StringLiteral,
+    Other,
+}
+
+impl From<&Tok> for TokenKind {
+    fn from(tok: &Tok) -> Self {
+        match tok {
+            Tok::Colon => TokenKind::Colon,
+            Tok::Lpar | Tok::Lsqb | Tok::Lbrace => TokenKind::OpenBracket,
+            Tok::Rpar | Tok::Rsqb | Tok::Rbrace => TokenKind::CloseBracket,
+            Tok::Indent => TokenKind::Indent,
+            Tok::String { .. } => TokenKind::StringLiteral,
+            _ => TokenKind::Other,
+        }
+    }
+}
+
+/// A single token in a [`TokenStream`].
+///
+/// Lexical errors are surfaced as data (`is_error`) rather than terminating
+/// iteration: a malformed span still gets a conservative start/end and an
+/// `Other` kind, so callers can choose to bail out instead of panicking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: Location,
+    pub end: Location,
+    pub is_error: bool,
+}
+
+impl Token {
+    pub fn range(&self) -> Range {
+        Range::new(self.start, self.end)
+    }
+}
+
+/// An error-tolerant tokenizer over a source slice.
+///
+/// In the spirit of `rustc_lexer`, this wraps [`lexer::lex_located`] but
+/// never stops at the first lexical error: every item is an infallible
+/// [`Token`], with errors recorded via [`Token::is_error`] instead of
+/// unwrapped away by `.flatten()` (which is what every caller in this crate
+/// used to do, silently dropping errors and risking a panic downstream).
+pub struct TokenStream<'a> {
+    inner: Box<dyn Iterator<Item = LexResult> + 'a>,
+}
+
+impl<'a> TokenStream<'a> {
+    pub fn new(source: &'a str, start: Location) -> Self {
+        Self {
+            inner: Box::new(lexer::lex_located(source, Mode::Module, start)),
+        }
+    }
+}
+
+impl Iterator for TokenStream<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        match self.inner.next()? {
+            Ok((start, tok, end)) => Some(Token {
+                kind: TokenKind::from(&tok),
+                start,
+                end,
+                is_error: false,
+            }),
+            Err(err) => Some(Token {
+                kind: TokenKind::Other,
+                start: err.location,
+                end: err.location,
+                is_error: true,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustpython_parser::ast::Location;
+
+    use super::{TokenKind, TokenStream};
+
+    #[test]
+    fn classifies_brackets_and_colon() {
+        let kinds: Vec<_> = TokenStream::new("if x:\n    pass", Location::new(1, 0))
+            .map(|token| token.kind)
+            .collect();
+        assert!(kinds.contains(&TokenKind::Colon));
+    }
+
+    #[test]
+    fn surfaces_errors_instead_of_dropping_them() {
+        let tokens: Vec<_> = TokenStream::new("x = \"unterminated", Location::new(1, 0)).collect();
+        assert!(tokens.iter().any(|token| token.is_error));
+    }
+}
@@ -0,0 +1,322 @@
+use rustpython_parser::ast::Location;
+
+use crate::core::types::Range;
+
+/// Which escape grammar applies to the body of a string or bytes literal.
+///
+/// Modeled on `rustc_lexer`'s `unescape` module: the mode determines which
+/// backslash escapes are legal and what kind of output they produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// A `str` literal, e.g. `"..."` or `'''...'''`.
+    Str,
+    /// A `bytes` literal, e.g. `b"..."`. Only ASCII characters may appear
+    /// literally; `\xHH` and octal escapes can produce any value up to 0xFF.
+    ByteStr,
+    /// A raw `str` literal, e.g. `r"..."`. Backslashes are literal.
+    RawStr,
+    /// A raw `bytes` literal, e.g. `rb"..."`. Backslashes are literal, and
+    /// only ASCII output is permitted.
+    RawByteStr,
+    /// The literal portion of an f-string body (i.e., everything outside of
+    /// `{...}` replacement fields). Shares `Str`'s escape grammar.
+    FString,
+}
+
+impl Mode {
+    /// Derive the unescape `Mode` from a quote prefix, as returned by
+    /// [`leading_quote`](super::helpers::leading_quote) (e.g. `rb"`, `F'''`).
+    pub fn from_prefix(prefix: &str) -> Mode {
+        let prefix = prefix.trim_end_matches(|c| c == '"' || c == '\'');
+        let is_raw = prefix.contains(['r', 'R']);
+        let is_bytes = prefix.contains(['b', 'B']);
+        let is_fstring = prefix.contains(['f', 'F']);
+        match (is_raw, is_bytes, is_fstring) {
+            (true, true, _) => Mode::RawByteStr,
+            (true, false, _) => Mode::RawStr,
+            (false, true, _) => Mode::ByteStr,
+            (false, false, true) => Mode::FString,
+            (false, false, false) => Mode::Str,
+        }
+    }
+
+    fn is_raw(self) -> bool {
+        matches!(self, Mode::RawStr | Mode::RawByteStr)
+    }
+
+    fn is_bytes(self) -> bool {
+        matches!(self, Mode::ByteStr | Mode::RawByteStr)
+    }
+}
+
+/// A malformed escape sequence encountered while unescaping a literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeError {
+    /// `\c` for some `c` that isn't one of Python's recognized escapes.
+    /// This is Python's W605 ("invalid escape sequence").
+    InvalidEscape,
+    /// `\xHH` without two valid hex digits following.
+    InvalidHexEscape,
+    /// `\uHHHH` without four valid hex digits following.
+    InvalidUnicode16Escape,
+    /// `\UHHHHHHHH` without eight valid hex digits following.
+    InvalidUnicode32Escape,
+    /// `\N{...}` with no closing brace, or an empty name.
+    InvalidNamedEscape,
+    /// A character or decoded codepoint that's outside of the legal range
+    /// for the mode (e.g., a non-ASCII literal character inside a `ByteStr`,
+    /// or a `\U` value above U+10FFFF).
+    OutOfRangeEscape,
+}
+
+/// Decode the interior of a string or bytes literal, invoking `callback` with
+/// the `Range` and decoded value (or error) of every character or escape
+/// sequence encountered.
+///
+/// `content` is the literal's body, with the surrounding quotes already
+/// stripped (see [`leading_quote`](super::helpers::leading_quote) and
+/// [`trailing_quote`](super::helpers::trailing_quote)); `prefix` is the
+/// literal's quote prefix, used to select a [`Mode`] via
+/// [`Mode::from_prefix`]; `location` is the source `Location` of the first
+/// byte of `content`.
+///
+/// In [`Mode::RawStr`] and [`Mode::RawByteStr`], `\` is always literal. In
+/// every other mode, `\n \t \r \a \b \f \v \\ \' \"`, `\xHH`, octal `\ooo`
+/// (including `\0`), and a backslash-newline continuation are decoded; in
+/// `Str` and `FString` mode, `\uHHHH`, `\UHHHHHHHH`, and `\N{NAME}` are
+/// decoded as well. Any other `\c` (including `\u`, `\U`, and `\N` in
+/// `ByteStr` mode) is reported via [`EscapeError::InvalidEscape`].
+pub fn unescape(
+    content: &str,
+    prefix: &str,
+    location: Location,
+    callback: &mut impl FnMut(Range, Result<char, EscapeError>),
+) {
+    let mode = Mode::from_prefix(prefix);
+    let mut chars = content.chars().peekable();
+    let mut row = location.row();
+    let mut column = location.column();
+
+    while let Some(c) = chars.next() {
+        let range_start = Location::new(row, column);
+        advance(c, &mut row, &mut column);
+
+        if c != '\\' || mode.is_raw() {
+            let result = if mode.is_bytes() && !c.is_ascii() {
+                Err(EscapeError::OutOfRangeEscape)
+            } else {
+                Ok(c)
+            };
+            callback(Range::new(range_start, Location::new(row, column)), result);
+            continue;
+        }
+
+        // A trailing, unescaped backslash at the end of the literal is
+        // treated literally (this can only happen in malformed source).
+        let Some(&next) = chars.peek() else {
+            callback(Range::new(range_start, Location::new(row, column)), Ok(c));
+            continue;
+        };
+        chars.next();
+        advance(next, &mut row, &mut column);
+
+        let result = match next {
+            '\n' => {
+                // Line continuation: the backslash and newline produce no
+                // output at all.
+                continue;
+            }
+            '\\' => Ok('\\'),
+            '\'' => Ok('\''),
+            '"' => Ok('"'),
+            'a' => Ok('\u{7}'),
+            'b' => Ok('\u{8}'),
+            'f' => Ok('\u{c}'),
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            'v' => Ok('\u{b}'),
+            '0'..='7' => {
+                let mut value = next.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    let Some(&digit) = chars.peek() else { break };
+                    let Some(digit_value) = digit.to_digit(8) else {
+                        break;
+                    };
+                    chars.next();
+                    advance(digit, &mut row, &mut column);
+                    value = value * 8 + digit_value;
+                }
+                // CPython truncates octal escapes to a single byte only in
+                // byte literals; `str`/f-string literals keep the full
+                // codepoint (e.g. `"\777"` is U+01FF, not U+00FF).
+                let value = if mode.is_bytes() { value & 0xFF } else { value };
+                char::from_u32(value).ok_or(EscapeError::OutOfRangeEscape)
+            }
+            'x' => read_hex_escape(&mut chars, 2, &mut row, &mut column)
+                .ok_or(EscapeError::InvalidHexEscape)
+                .and_then(|value| {
+                    if mode.is_bytes() && value > 0xFF {
+                        Err(EscapeError::OutOfRangeEscape)
+                    } else {
+                        char::from_u32(value).ok_or(EscapeError::OutOfRangeEscape)
+                    }
+                }),
+            'u' if !mode.is_bytes() => read_hex_escape(&mut chars, 4, &mut row, &mut column)
+                .ok_or(EscapeError::InvalidUnicode16Escape)
+                .and_then(|value| char::from_u32(value).ok_or(EscapeError::OutOfRangeEscape)),
+            'U' if !mode.is_bytes() => read_hex_escape(&mut chars, 8, &mut row, &mut column)
+                .ok_or(EscapeError::InvalidUnicode32Escape)
+                .and_then(|value| char::from_u32(value).ok_or(EscapeError::OutOfRangeEscape)),
+            'N' if !mode.is_bytes() => read_named_escape(&mut chars, &mut row, &mut column),
+            _ => Err(EscapeError::InvalidEscape),
+        };
+
+        callback(Range::new(range_start, Location::new(row, column)), result);
+    }
+}
+
+/// Read exactly `count` hex digits, advancing `row`/`column` as we go.
+/// Returns `None` if fewer than `count` valid hex digits are available.
+fn read_hex_escape(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    count: usize,
+    row: &mut usize,
+    column: &mut usize,
+) -> Option<u32> {
+    let mut value = 0u32;
+    for _ in 0..count {
+        let &digit = chars.peek()?;
+        let digit_value = digit.to_digit(16)?;
+        chars.next();
+        advance(digit, row, column);
+        value = value * 16 + digit_value;
+    }
+    Some(value)
+}
+
+/// Read a `\N{NAME}` escape. We don't carry a Unicode name database, so we
+/// only validate the syntax (a non-empty name closed by `}`); on success, we
+/// decode to the replacement character as a placeholder for the real
+/// codepoint, which is enough for rules that merely need to know the escape
+/// was well-formed.
+fn read_named_escape(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    row: &mut usize,
+    column: &mut usize,
+) -> Result<char, EscapeError> {
+    if chars.peek() != Some(&'{') {
+        return Err(EscapeError::InvalidNamedEscape);
+    }
+    chars.next();
+    advance('{', row, column);
+
+    let mut name_len = 0;
+    loop {
+        match chars.next() {
+            Some('}') => {
+                advance('}', row, column);
+                break;
+            }
+            Some(c) => {
+                advance(c, row, column);
+                name_len += 1;
+            }
+            None => return Err(EscapeError::InvalidNamedEscape),
+        }
+    }
+
+    if name_len == 0 {
+        Err(EscapeError::InvalidNamedEscape)
+    } else {
+        Ok('\u{FFFD}')
+    }
+}
+
+fn advance(c: char, row: &mut usize, column: &mut usize) {
+    if c == '\n' {
+        *row += 1;
+        *column = 0;
+    } else {
+        *column += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustpython_parser::ast::Location;
+
+    use crate::core::types::Range;
+
+    use super::{unescape, EscapeError, Mode};
+
+    fn collect(content: &str, prefix: &str) -> Vec<(Range, Result<char, EscapeError>)> {
+        let mut out = Vec::new();
+        unescape(content, prefix, Location::new(1, 0), &mut |range, result| {
+            out.push((range, result));
+        });
+        out
+    }
+
+    #[test]
+    fn valid_escapes_decode() {
+        let decoded: String = collect(r"a\nb\t", "\"")
+            .into_iter()
+            .map(|(_, result)| result.unwrap())
+            .collect();
+        assert_eq!(decoded, "a\nb\t");
+    }
+
+    #[test]
+    fn invalid_escape_is_flagged() {
+        let results = collect(r"\d", "\"");
+        assert_eq!(results[0].1, Err(EscapeError::InvalidEscape));
+    }
+
+    #[test]
+    fn raw_mode_treats_backslash_literally() {
+        let decoded: String = collect(r"\d", "r\"")
+            .into_iter()
+            .map(|(_, result)| result.unwrap())
+            .collect();
+        assert_eq!(decoded, r"\d");
+    }
+
+    #[test]
+    fn octal_escape_is_not_truncated_outside_bytes_mode() {
+        let decoded: String = collect(r"\777", "\"")
+            .into_iter()
+            .map(|(_, result)| result.unwrap())
+            .collect();
+        assert_eq!(decoded, "\u{1FF}");
+    }
+
+    #[test]
+    fn octal_escape_is_truncated_in_bytes_mode() {
+        let decoded: String = collect(r"\777", "b\"")
+            .into_iter()
+            .map(|(_, result)| result.unwrap())
+            .collect();
+        assert_eq!(decoded, "\u{FF}");
+    }
+
+    #[test]
+    fn non_ascii_literal_char_is_out_of_range_in_bytes_mode() {
+        let results = collect("é", "b\"");
+        assert_eq!(results[0].1, Err(EscapeError::OutOfRangeEscape));
+    }
+
+    #[test]
+    fn non_ascii_literal_char_is_fine_in_str_mode() {
+        let results = collect("é", "\"");
+        assert_eq!(results[0].1, Ok('é'));
+    }
+
+    #[test]
+    fn mode_from_prefix() {
+        assert_eq!(Mode::from_prefix("\""), Mode::Str);
+        assert_eq!(Mode::from_prefix("b\""), Mode::ByteStr);
+        assert_eq!(Mode::from_prefix("rb\""), Mode::RawByteStr);
+        assert_eq!(Mode::from_prefix("f\""), Mode::FString);
+    }
+}